@@ -0,0 +1,128 @@
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG_FILE_NAME: &str = "bindings.toml";
+
+/// Everything a key press or mouse click can trigger. Keeping this as a flat
+/// enum (instead of calling App methods straight from the event loop) is what
+/// lets bindings be remapped without touching the match arms that drive them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Action {
+    ToggleRunning,
+    Quit,
+    FasterTick,
+    SlowerTick,
+    AddCell,
+    RemoveCell,
+    ClearBoard,
+    Step,
+    LoadPattern,
+    SavePattern,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    #[serde(default = "KeyModifiers::empty")]
+    pub mods: KeyModifiers,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MouseBinding {
+    pub button: MouseButton,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Bindings {
+    #[serde(default = "default_keys")]
+    pub keys: Vec<KeyBinding>,
+    #[serde(default = "default_mouse")]
+    pub mouse: Vec<MouseBinding>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings {
+            keys: default_keys(),
+            mouse: default_mouse(),
+        }
+    }
+}
+
+fn default_keys() -> Vec<KeyBinding> {
+    vec![
+        KeyBinding {
+            code: KeyCode::Enter,
+            mods: KeyModifiers::empty(),
+            action: Action::ToggleRunning,
+        },
+        KeyBinding {
+            code: KeyCode::Char('q'),
+            mods: KeyModifiers::empty(),
+            action: Action::Quit,
+        },
+        KeyBinding {
+            code: KeyCode::Char('+'),
+            mods: KeyModifiers::empty(),
+            action: Action::FasterTick,
+        },
+        KeyBinding {
+            code: KeyCode::Char('-'),
+            mods: KeyModifiers::empty(),
+            action: Action::SlowerTick,
+        },
+        KeyBinding {
+            code: KeyCode::Char('c'),
+            mods: KeyModifiers::empty(),
+            action: Action::ClearBoard,
+        },
+        KeyBinding {
+            code: KeyCode::Char('s'),
+            mods: KeyModifiers::empty(),
+            action: Action::Step,
+        },
+        KeyBinding {
+            code: KeyCode::Char('l'),
+            mods: KeyModifiers::empty(),
+            action: Action::LoadPattern,
+        },
+        KeyBinding {
+            code: KeyCode::Char('w'),
+            mods: KeyModifiers::empty(),
+            action: Action::SavePattern,
+        },
+    ]
+}
+
+fn default_mouse() -> Vec<MouseBinding> {
+    vec![
+        MouseBinding {
+            button: MouseButton::Left,
+            action: Action::AddCell,
+        },
+        MouseBinding {
+            button: MouseButton::Right,
+            action: Action::RemoveCell,
+        },
+    ]
+}
+
+fn config_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rust-game-of-life")
+        .join(CONFIG_FILE_NAME)
+}
+
+/// Loads bindings from the user's config directory, falling back to the
+/// built-in defaults above if the file is missing or fails to parse.
+pub fn load() -> Bindings {
+    fs::read_to_string(config_path())
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,102 @@
+//! The pure Game of Life core: simulation rules plus the one render-facing
+//! query a frontend needs, with no crossterm/tui dependency. This is what
+//! lets the core be unit-tested directly and, in principle, driven by a
+//! frontend other than the tui-rs binary in `main.rs`.
+
+pub mod simulation;
+
+use simulation::Simulation;
+
+/// A minimal stand-in for `tui::layout::Rect` -- just enough to describe a
+/// rendering window without pulling tui into this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Yields every cell in `viewport`, in row-major order, alongside whether
+/// it's alive. This is the only thing a frontend needs to draw a frame, so
+/// it's the one place the core and a renderer have to agree on anything.
+pub fn renderable_content(state: &dyn Simulation, viewport: Rect) -> impl Iterator<Item = (u16, u16, bool)> {
+    let mut cells = vec![false; viewport.width as usize * viewport.height as usize];
+    let x_range = viewport.x as i64..viewport.x as i64 + viewport.width as i64;
+    let y_range = viewport.y as i64..viewport.y as i64 + viewport.height as i64;
+
+    for (x, y) in state.live_cells() {
+        if x_range.contains(&x) && y_range.contains(&y) {
+            let col = (x - viewport.x as i64) as usize;
+            let row = (y - viewport.y as i64) as usize;
+            cells[row * viewport.width as usize + col] = true;
+        }
+    }
+
+    let width = viewport.width as usize;
+    let (x0, y0) = (viewport.x, viewport.y);
+    cells.into_iter().enumerate().map(move |(i, alive)| {
+        let col = (i % width) as u16;
+        let row = (i / width) as u16;
+        (x0 + col, y0 + row, alive)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simulation::{new as new_simulation, SimulationBackend};
+
+    #[test]
+    fn block_stays_static() {
+        let mut state = new_simulation(SimulationBackend::Sparse);
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            state.set(x, y, true);
+        }
+
+        let before: std::collections::HashSet<_> = state.live_cells().into_iter().collect();
+        state.step();
+        let after: std::collections::HashSet<_> = state.live_cells().into_iter().collect();
+
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let mut state = new_simulation(SimulationBackend::Sparse);
+        for (x, y) in [(0, 1), (1, 1), (2, 1)] {
+            state.set(x, y, true);
+        }
+
+        let horizontal: std::collections::HashSet<_> = state.live_cells().into_iter().collect();
+        state.step();
+        let vertical: std::collections::HashSet<_> = state.live_cells().into_iter().collect();
+        state.step();
+        let back_to_horizontal: std::collections::HashSet<_> = state.live_cells().into_iter().collect();
+
+        assert_ne!(horizontal, vertical);
+        assert_eq!(horizontal, back_to_horizontal);
+    }
+
+    #[test]
+    fn renderable_content_matches_live_cells_within_viewport() {
+        let mut state = new_simulation(SimulationBackend::Sparse);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            state.set(x, y, true);
+        }
+        let viewport = Rect {
+            x: 0,
+            y: 0,
+            width: 4,
+            height: 4,
+        };
+
+        let rendered: std::collections::HashSet<_> = renderable_content(state.as_ref(), viewport)
+            .filter(|&(_, _, alive)| alive)
+            .map(|(x, y, _)| (x as i64, y as i64))
+            .collect();
+        let live: std::collections::HashSet<_> = state.live_cells().into_iter().collect();
+
+        assert_eq!(rendered, live);
+    }
+}
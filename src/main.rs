@@ -1,4 +1,5 @@
 use crossterm::{
+    cursor::Show,
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, MouseButton,
         MouseEvent, MouseEventKind,
@@ -7,8 +8,9 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use std::{
-    error, io,
-    sync::mpsc::{self, Sender},
+    error, fs, io,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
     thread,
     time::{Duration, Instant},
 };
@@ -22,6 +24,13 @@ use tui::{
     Frame, Terminal,
 };
 
+mod bindings;
+mod pattern;
+
+use bindings::{Action, Bindings};
+use rust_game_of_life::simulation::{self, Simulation, SimulationBackend};
+use rust_game_of_life::{renderable_content, Rect as CoreRect};
+
 const THIN_MARGIN: &Margin = &Margin {
     horizontal: 1,
     vertical: 1,
@@ -40,6 +49,43 @@ impl Point {
             && self.y >= rect.y
             && self.y < rect.y + rect.height
     }
+
+    // Bresenham's line algorithm: step along the major axis, accumulate error,
+    // and advance the minor axis whenever the error crosses zero. Used to fill
+    // in the gap between two drag samples, since motion reports are sparse.
+    fn line_to(self, other: Point) -> Vec<Point> {
+        let (mut x0, mut y0) = (self.x as i32, self.y as i32);
+        let (x1, y1) = (other.x as i32, other.y as i32);
+
+        let dx = (x1 - x0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let dy = -(y1 - y0).abs();
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        let mut points = Vec::new();
+        loop {
+            points.push(Point {
+                x: x0 as u16,
+                y: y0 as u16,
+            });
+
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+        points
+    }
 }
 
 type ClickPosition = Point;
@@ -50,27 +96,67 @@ enum ClickType {
     Right,
 }
 
+impl ClickType {
+    fn button(&self) -> MouseButton {
+        match self {
+            ClickType::Left => MouseButton::Left,
+            ClickType::Right => MouseButton::Right,
+        }
+    }
+}
+
 const DEFAULT_TICK: Duration = Duration::from_millis(250);
 const TICK_STEP: Duration = Duration::from_millis(10);
 
 enum GameEvent {
     KeyInput(KeyEvent),
     Click(ClickType, ClickPosition),
+    Drag(ClickType, ClickPosition),
+    DragEnd,
     Tick,
-    TickSet(Duration),
     Resize(Rect),
+    LoadPattern(PathBuf),
+    SavePattern(PathBuf),
+}
+
+enum PromptKind {
+    Load,
+    Save,
+}
+
+// Backs the filename prompt drawn in the controls pane while a load/save is pending.
+struct Prompt {
+    kind: PromptKind,
+    buffer: String,
 }
 
 struct App {
-    state: Vec<Vec<bool>>,
+    state: Box<dyn Simulation>,
+    backend: SimulationBackend,
     running: bool,
+    should_quit: bool,
     tick_rate: Duration,
     dimensions: Rect,
     last_click: (usize, usize),
+    last_drag_cell: Option<Point>,
+    bindings: Bindings,
+    tick_rate_tx: Sender<Duration>,
+    prompt: Option<Prompt>,
+    status: Option<String>,
+}
+
+// Picked once at startup via GOL_BACKEND=hashlife; sparse is the right
+// default for interactive drawing, hashlife for fast-forwarding huge,
+// regular patterns.
+fn simulation_backend() -> SimulationBackend {
+    match std::env::var("GOL_BACKEND").as_deref() {
+        Ok("hashlife") => SimulationBackend::Hashlife,
+        _ => SimulationBackend::Sparse,
+    }
 }
 
 impl App {
-    fn new<B: Backend>(frame: Frame<B>) -> App {
+    fn new<B: Backend>(frame: Frame<B>, tick_rate_tx: Sender<Duration>) -> App {
         let dimensions = Layout::default()
             .direction(Direction::Horizontal)
             .margin(2)
@@ -78,27 +164,30 @@ impl App {
             .split(frame.size())[0]
             .inner(THIN_MARGIN);
 
+        let backend = simulation_backend();
         App {
-            state: vec![vec![false; dimensions.width as usize + 1]; dimensions.height as usize + 1],
+            state: simulation::new(backend),
+            backend,
             running: false,
+            should_quit: false,
             tick_rate: DEFAULT_TICK,
             dimensions,
             // TODO: For debugging purposes, delete later
             last_click: (0, 0),
+            last_drag_cell: None,
+            bindings: bindings::load(),
+            tick_rate_tx,
+            prompt: None,
+            status: None,
         }
     }
 
     fn resize(&mut self, rect: Rect) {
-        // we need to remove borders, again
-        let dimensions = rect.inner(THIN_MARGIN);
-
-        *self = App {
-            state: vec![vec![false; dimensions.width as usize + 1]; dimensions.height as usize + 1],
-            running: false,
-            tick_rate: self.tick_rate,
-            dimensions,
-            last_click: self.last_click,
-        }
+        // we need to remove borders, again; the simulation itself is
+        // independent of viewport size, so it survives the resize untouched
+        self.dimensions = rect.inner(THIN_MARGIN);
+        self.running = false;
+        self.last_drag_cell = None;
     }
 
     fn on_tick(&mut self) {
@@ -106,86 +195,110 @@ impl App {
             return;
         }
 
-        // We don't want to effect any changes until all cells are evaluated
-        let mut to_flip: Vec<(usize, usize)> = Vec::new();
-        for (row_idx, row) in self.state.iter().enumerate() {
-            for (col_idx, cell) in row.iter().enumerate() {
-                // The data type wrangling in this area is atrocious, I wonder if there's any way to fix it
-                // The + self.dimensions.w/h is to prevent overflow, not required for adding
-                let neighbour_idxs = {
-                    let top_row_idx = (row_idx + self.dimensions.height as usize - 1)
-                        % self.dimensions.height as usize;
-                    let bottom_row_idx = (row_idx as usize + 1) % self.dimensions.height as usize;
-                    let left_col_idx = (col_idx + self.dimensions.width as usize - 1)
-                        % self.dimensions.width as usize;
-                    let right_col_idx = (col_idx as usize + 1) % self.dimensions.width as usize;
-
-                    [
-                        (top_row_idx, left_col_idx),
-                        (top_row_idx, col_idx),
-                        (top_row_idx, right_col_idx),
-                        (row_idx, left_col_idx),
-                        (row_idx, right_col_idx),
-                        (bottom_row_idx, left_col_idx),
-                        (bottom_row_idx, col_idx),
-                        (bottom_row_idx, right_col_idx),
-                    ]
-                };
-
-                let live_neighbour_count = neighbour_idxs
-                    .iter()
-                    .filter(|(nbr_row_idx, nbr_col_idx)| self.state[*nbr_row_idx][*nbr_col_idx])
-                    .count();
-
-                let mut flip_this = false;
-                match (cell, live_neighbour_count) {
-                    /* By this point the formatting or the game rules is starting to look weird,
-                      but I can defend my decisions.
-
-                      Why I don't like particularly is how I'm using a match statement which leads
-                      to one of two possible decisions: set needs_flip to true, or do nothing.
-                      Doing one thing or nothing (and skipping all the extra conditionals once we
-                      reach a truthy evaluation) sounds like the job for a if/else if decision tree.
-                      I wanted to go with a match because the pattern matching and guards make for
-                      an idiomatic overview of the game rules.
-
-                      If we decide to settle on a match then its more idiomatic use would be to set
-                      the literal boolean for each cell in self.state, but that would involve
-                      a lot of unnecessary writes to the Vec.
-
-                      The flip_this boolean could be made redundant by push to to_flip
-                      directly but that would clutter the match.
-
-                      Any potential implementation would be faster and cleaner to implement
-                      than taking the time to write this comment.
-                      But this is a learning experience, and I'm a perfectionist.
-                    */
-                    (true, count) if count < 2 => flip_this = true, // Underpopulation
-                    (true, count) if count > 3 => flip_this = true, // Overpopulation
-                    (false, count) if count == 3 => flip_this = true, // Reproduction
-                    (_, _) => {}
-                }
-
-                if flip_this {
-                    to_flip.push((row_idx, col_idx))
-                }
-            }
-        }
+        self.step();
+    }
 
-        to_flip.iter().for_each(|(row_idx, col_idx)| {
-            self.state[*row_idx][*col_idx] = !self.state[*row_idx][*col_idx]
-        });
+    fn step(&mut self) {
+        self.state.step();
     }
 
     fn add_cell(&mut self, pos: ClickPosition) {
-        self.state[pos.y as usize][pos.x as usize] = true;
+        self.state.set(pos.x as i64, pos.y as i64, true);
     }
     fn remove_cell(&mut self, pos: ClickPosition) {
-        self.state[pos.y as usize][pos.x as usize] = false;
+        self.state.set(pos.x as i64, pos.y as i64, false);
+    }
+
+    // Fills in every cell on the line between `from` and `to` so a fast drag
+    // doesn't leave dotted gaps between sparse motion reports. Goes through
+    // the same binding lookup as a single click so remapping a mouse button
+    // affects drags too, not just clicks.
+    fn paint_line(&mut self, from: Point, to: Point, button: &ClickType) {
+        let action = self
+            .bindings
+            .mouse
+            .iter()
+            .find(|binding| binding.button == button.button())
+            .map(|binding| binding.action);
+
+        let action = match action {
+            Some(action) => action,
+            None => return,
+        };
+
+        for point in from.line_to(to) {
+            if !point.in_rect(self.dimensions) {
+                continue;
+            }
+
+            let offset_point = Point {
+                x: point.x - self.dimensions.x,
+                y: point.y - self.dimensions.y,
+            };
+
+            self.dispatch(action, Some(offset_point));
+        }
+    }
+
+    // Single entry point every bound key/mouse action runs through, so
+    // remapping a binding never means adding another match arm in the event loop.
+    fn dispatch(&mut self, action: Action, position: Option<ClickPosition>) {
+        match action {
+            Action::ToggleRunning => self.running = !self.running,
+            Action::Quit => self.should_quit = true,
+            Action::FasterTick => {
+                if self.tick_rate > Duration::from_millis(30) {
+                    self.tick_rate -= TICK_STEP;
+                    let _ = self.tick_rate_tx.send(self.tick_rate);
+                }
+            }
+            Action::SlowerTick => {
+                self.tick_rate += TICK_STEP;
+                let _ = self.tick_rate_tx.send(self.tick_rate);
+            }
+            Action::AddCell => {
+                if let Some(position) = position {
+                    self.add_cell(position);
+                }
+            }
+            Action::RemoveCell => {
+                if let Some(position) = position {
+                    self.remove_cell(position);
+                }
+            }
+            Action::ClearBoard => self.state.clear(),
+            Action::Step => self.step(),
+            Action::LoadPattern => {
+                self.prompt = Some(Prompt {
+                    kind: PromptKind::Load,
+                    buffer: String::new(),
+                });
+            }
+            Action::SavePattern => {
+                self.prompt = Some(Prompt {
+                    kind: PromptKind::Save,
+                    buffer: String::new(),
+                });
+            }
+        }
     }
 }
 
+// A panic anywhere past this point would otherwise leave the terminal stuck
+// in raw mode on the alternate screen, mangling the panic message and forcing
+// a blind `reset`. Restore it first, then hand off to the default hook so the
+// backtrace still prints normally.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Show);
+        default_hook(panic_info);
+    }));
+}
+
 fn main() -> Result<(), Box<dyn error::Error>> {
+    install_panic_hook();
     enable_raw_mode().expect("Can enter raw mode");
 
     let mut stdout = io::stdout();
@@ -193,8 +306,9 @@ fn main() -> Result<(), Box<dyn error::Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let app = App::new(terminal.get_frame());
-    let res = run_app(&mut terminal, app);
+    let (tick_rate_tx, tick_rate_rx) = mpsc::channel();
+    let app = App::new(terminal.get_frame(), tick_rate_tx);
+    let res = run_app(&mut terminal, app, tick_rate_rx);
 
     disable_raw_mode()?;
     execute!(
@@ -214,91 +328,153 @@ fn main() -> Result<(), Box<dyn error::Error>> {
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: App,
+    tick_rate_rx: Receiver<Duration>,
 ) -> Result<(), Box<dyn error::Error>> {
     let (mut tx, rx) = mpsc::channel();
 
     let event_handler_tx = tx.clone();
-    thread::spawn(move || handle_game_events(event_handler_tx));
-
-    let mut exit = false;
+    thread::spawn(move || handle_game_events(event_handler_tx, tick_rate_rx));
 
     loop {
         terminal.draw(|f| ui(f, &mut app, &mut tx))?;
 
-        match rx.recv().unwrap() {
-            // GameEvent handler/consumer
-            GameEvent::KeyInput(event) => match event.code {
-                KeyCode::Enter => app.running = !app.running,
+        // Block for the first event so the thread parks while idle, then
+        // drain whatever else is already queued without blocking. At fast
+        // tick rates this is where the backlog would otherwise pile up:
+        // instead of redrawing once per queued Tick, every queued Tick is
+        // coalesced into one batched advance before the next draw.
+        let mut event = rx.recv().unwrap();
+        let mut queued_ticks = 0;
+        loop {
+            if let GameEvent::Tick = event {
+                queued_ticks += 1;
+            } else {
+                handle_event(&mut app, &mut tx, event);
+            }
+
+            match rx.try_recv() {
+                Ok(next) => event = next,
+                Err(_) => break,
+            }
+        }
 
-                KeyCode::Char('q') => exit = true,
+        for _ in 0..queued_ticks {
+            app.on_tick();
+        }
 
-                _ => {}
-            },
+        if app.should_quit {
+            break;
+        }
+    }
+    Ok(())
+}
 
-            GameEvent::Click(button, position)
-                if !app.running && position.in_rect(app.dimensions) =>
-            {
-                let x_offset = app.dimensions.x;
-                let y_offset = app.dimensions.y;
-                let offset_position = ClickPosition {
-                    x: position.x - x_offset,
-                    y: position.y - y_offset,
+// Applies every GameEvent other than Tick, which `run_app` batches itself.
+fn handle_event(app: &mut App, tx: &mut Sender<GameEvent>, event: GameEvent) {
+    match event {
+        // GameEvent handler/consumer
+        GameEvent::KeyInput(event) if app.prompt.is_some() => match event.code {
+            KeyCode::Enter => {
+                let prompt = app.prompt.take().unwrap();
+                let path = PathBuf::from(prompt.buffer);
+                let pattern_event = match prompt.kind {
+                    PromptKind::Load => GameEvent::LoadPattern(path),
+                    PromptKind::Save => GameEvent::SavePattern(path),
                 };
-                app.last_click = (position.x as usize, position.y as usize);
+                tx.send(pattern_event)
+                    .expect("Pattern events can be sent to the consumer");
+            }
+            KeyCode::Esc => app.prompt = None,
+            KeyCode::Backspace => {
+                app.prompt.as_mut().unwrap().buffer.pop();
+            }
+            KeyCode::Char(c) => app.prompt.as_mut().unwrap().buffer.push(c),
+            _ => {}
+        },
+
+        GameEvent::KeyInput(event) => {
+            if let Some(binding) = app
+                .bindings
+                .keys
+                .iter()
+                .find(|binding| binding.code == event.code && binding.mods == event.modifiers)
+            {
+                app.dispatch(binding.action, None);
+            }
+        }
 
-                if button == ClickType::Left {
-                    app.add_cell(offset_position);
-                } else {
-                    app.remove_cell(offset_position);
-                }
+        GameEvent::Click(button, position) if !app.running && position.in_rect(app.dimensions) => {
+            let x_offset = app.dimensions.x;
+            let y_offset = app.dimensions.y;
+            let offset_position = ClickPosition {
+                x: position.x - x_offset,
+                y: position.y - y_offset,
+            };
+            app.last_click = (position.x as usize, position.y as usize);
+
+            if let Some(binding) = app
+                .bindings
+                .mouse
+                .iter()
+                .find(|binding| binding.button == button.button())
+            {
+                app.dispatch(binding.action, Some(offset_position));
             }
+        }
 
-            GameEvent::Tick => app.on_tick(),
-            GameEvent::TickSet(new_tick_rate) => app.tick_rate = new_tick_rate,
-            GameEvent::Resize(rect) => app.resize(rect),
+        GameEvent::Drag(button, position) if !app.running && position.in_rect(app.dimensions) => {
+            let from = app.last_drag_cell.unwrap_or(position);
+            app.paint_line(from, position, &button);
+            app.last_drag_cell = Some(position);
+        }
 
-            _ => {}
+        GameEvent::DragEnd => app.last_drag_cell = None,
+
+        // Coalesced and applied by run_app before handle_event ever sees one.
+        GameEvent::Tick => {}
+        GameEvent::Resize(rect) => app.resize(rect),
+
+        GameEvent::LoadPattern(path) => {
+            let width = app.dimensions.width as i64;
+            let height = app.dimensions.height as i64;
+            app.status = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|contents| pattern::parse(&contents).map_err(|err| err.to_string()))
+                .map(|cells| pattern::stamp_centered(app.state.as_mut(), &cells, width, height))
+                .err();
         }
 
-        if exit {
-            break;
+        GameEvent::SavePattern(path) => {
+            app.status = fs::write(&path, pattern::export_rle(app.state.as_ref()))
+                .err()
+                .map(|err| err.to_string());
         }
+
+        _ => {}
     }
-    Ok(())
 }
 
-fn handle_game_events(tx: Sender<GameEvent>) {
+fn handle_game_events(tx: Sender<GameEvent>, tick_rate_rx: Receiver<Duration>) {
     // Reads for inputs and generates ticks
     let mut tick_rate = DEFAULT_TICK;
     let mut last_tick = Instant::now();
 
     loop {
+        // The consumer is the source of truth for the tick rate now that it's
+        // configurable through bindings, so pick up whatever it last dispatched.
+        while let Ok(new_tick_rate) = tick_rate_rx.try_recv() {
+            tick_rate = new_tick_rate;
+        }
+
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if event::poll(timeout).expect("Events are properly polled") {
             match event::read().expect("Key inputs are detected") {
-                Event::Key(key) => match key.code {
-                    KeyCode::Char('+') => {
-                        tick_rate += TICK_STEP;
-
-                        tx.send(GameEvent::TickSet(tick_rate))
-                            .expect("Can increase tick rate");
-                    }
-                    KeyCode::Char('-') => {
-                        if tick_rate > Duration::from_millis(30) {
-                            // TODO: Figure out a way to drop events so the buffer doesn't get clogged with ticks at really high rates
-                            tick_rate -= TICK_STEP
-                        }
-
-                        tx.send(GameEvent::TickSet(tick_rate))
-                            .expect("Can increase tick rate");
-                    }
-                    _ => tx
-                        .send(GameEvent::KeyInput(key))
-                        .expect("GameEvent keys can be sent to the consumer"),
-                },
+                Event::Key(key) => tx
+                    .send(GameEvent::KeyInput(key))
+                    .expect("GameEvent keys can be sent to the consumer"),
 
                 Event::Mouse(MouseEvent {
                     kind:
@@ -321,6 +497,34 @@ fn handle_game_events(tx: Sender<GameEvent>) {
                     .expect("Clicks can be sent to the consumer");
                 }
 
+                Event::Mouse(MouseEvent {
+                    kind:
+                        button @ (MouseEventKind::Drag(MouseButton::Left)
+                        | MouseEventKind::Drag(MouseButton::Right)),
+                    column,
+                    row,
+                    ..
+                }) => {
+                    let click_type = if button == MouseEventKind::Drag(MouseButton::Left) {
+                        ClickType::Left
+                    } else {
+                        ClickType::Right
+                    };
+
+                    tx.send(GameEvent::Drag(
+                        click_type,
+                        ClickPosition { x: column, y: row },
+                    ))
+                    .expect("Drags can be sent to the consumer");
+                }
+
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Up(_),
+                    ..
+                }) => tx
+                    .send(GameEvent::DragEnd)
+                    .expect("Drag ends can be sent to the consumer"),
+
                 _ => {}
             }
         }
@@ -347,17 +551,16 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App, event_tx: &mut Sender<GameEve
     /* A tui-rs Canvas sounds like the more obvious tool for this case
       but that dot system doesn't conform to a simple grid system like Paragrah does
     */
+    let viewport = CoreRect {
+        x: 0,
+        y: 0,
+        width: app.dimensions.width,
+        height: app.dimensions.height,
+    };
+
     let game = Paragraph::new(vec![Spans::from(vec![Span::styled(
-        app.state
-            .iter()
-            .flat_map(|row| {
-                row.iter().map(|x| {
-                    if *x {
-                        return "â–ˆ";
-                    }
-                    " "
-                })
-            })
+        renderable_content(app.state.as_ref(), viewport)
+            .map(|(_, _, alive)| if alive { "â–ˆ" } else { " " })
             .collect::<String>(),
         Style::default().add_modifier(Modifier::BOLD),
     )])])
@@ -373,7 +576,7 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App, event_tx: &mut Sender<GameEve
         true => "  Pause",
     };
 
-    let instructions = Paragraph::new(vec![
+    let mut lines = vec![
         Spans::from(vec![Span::raw("")]),
         Spans::from(vec![Span::raw(" [Left click]")]),
         Spans::from(vec![Span::raw("  Add cell")]),
@@ -390,10 +593,43 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App, event_tx: &mut Sender<GameEve
             app.tick_rate.as_millis()
         ))]),
         Spans::from(vec![Span::raw("")]),
+    ];
+
+    if app.backend == SimulationBackend::Hashlife {
+        lines.push(Spans::from(vec![Span::raw(
+            " Hashlife: jumps several generations per tick, growing over time --",
+        )]));
+        lines.push(Spans::from(vec![Span::raw(
+            " tick rate is not generations/sec here.",
+        )]));
+        lines.push(Spans::from(vec![Span::raw("")]));
+    }
+
+    lines.extend([
+        Spans::from(vec![Span::raw(" [l, w]")]),
+        Spans::from(vec![Span::raw("  Load/save pattern")]),
+        Spans::from(vec![Span::raw("")]),
         Spans::from(vec![Span::raw(" [q]")]),
         Spans::from(vec![Span::raw("  Exit")]),
-    ])
-    .alignment(Alignment::Left)
-    .block(Block::default().borders(Borders::ALL).title("Controls"));
+    ]);
+
+    if let Some(prompt) = &app.prompt {
+        let label = match prompt.kind {
+            PromptKind::Load => "Load path",
+            PromptKind::Save => "Save path",
+        };
+        lines.push(Spans::from(vec![Span::raw("")]));
+        lines.push(Spans::from(vec![Span::raw(format!(
+            " {}: {}_",
+            label, prompt.buffer
+        ))]));
+    } else if let Some(status) = &app.status {
+        lines.push(Spans::from(vec![Span::raw("")]));
+        lines.push(Spans::from(vec![Span::raw(format!(" {}", status))]));
+    }
+
+    let instructions = Paragraph::new(lines)
+        .alignment(Alignment::Left)
+        .block(Block::default().borders(Borders::ALL).title("Controls"));
     f.render_widget(instructions, chunks[1]);
 }
@@ -0,0 +1,176 @@
+use rust_game_of_life::simulation::Simulation;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Coordinates are signed so patterns can be parsed without first knowing
+/// where they'll land in the (always non-negative) viewport.
+type Cells = HashSet<(i64, i64)>;
+
+#[derive(Debug)]
+pub enum PatternError {
+    UnknownFormat,
+    Parse(String),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::UnknownFormat => write!(f, "unrecognized pattern format"),
+            PatternError::Parse(msg) => write!(f, "failed to parse pattern: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// Parses either Life 1.06 (`#Life 1.06` header, one `x y` pair per line) or
+/// RLE (`x = m, y = n, rule = ...` header, run-length-encoded body) into the
+/// set of live coordinates it describes.
+pub fn parse(contents: &str) -> Result<Cells, PatternError> {
+    let trimmed = contents.trim_start();
+
+    if trimmed.starts_with("#Life 1.06") {
+        parse_life_106(trimmed)
+    } else if trimmed.lines().any(is_rle_header) {
+        parse_rle(trimmed)
+    } else {
+        Err(PatternError::UnknownFormat)
+    }
+}
+
+// Matches "x = 3, y = 3, rule = B3/S23" as well as the space-less
+// "x=3,y=3,rule=B3/S23" some tools emit.
+fn is_rle_header(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix('x')
+        .map(|rest| rest.trim_start().starts_with('='))
+        .unwrap_or(false)
+}
+
+fn parse_life_106(contents: &str) -> Result<Cells, PatternError> {
+    contents
+        .lines()
+        .skip(1) // the "#Life 1.06" header
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut coords = line.split_whitespace();
+            let x = coords.next().and_then(|v| v.parse().ok());
+            let y = coords.next().and_then(|v| v.parse().ok());
+
+            x.zip(y)
+                .ok_or_else(|| PatternError::Parse(line.to_string()))
+        })
+        .collect()
+}
+
+fn parse_rle(contents: &str) -> Result<Cells, PatternError> {
+    let mut cells = Cells::new();
+    let mut x = 0i64;
+    let mut y = 0i64;
+    let mut run = String::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || is_rle_header(line) {
+            continue;
+        }
+
+        for ch in line.chars() {
+            match ch {
+                '0'..='9' => run.push(ch),
+                'b' | 'o' | '$' => {
+                    let count: i64 = if run.is_empty() {
+                        1
+                    } else {
+                        run.parse()
+                            .map_err(|_| PatternError::Parse(run.clone()))?
+                    };
+                    run.clear();
+
+                    match ch {
+                        'b' => x += count,
+                        'o' => {
+                            for _ in 0..count {
+                                cells.insert((x, y));
+                                x += 1;
+                            }
+                        }
+                        '$' => {
+                            y += count;
+                            x = 0;
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                '!' => return Ok(cells),
+                _ => return Err(PatternError::Parse(format!("unexpected character '{}'", ch))),
+            }
+        }
+    }
+
+    Ok(cells)
+}
+
+/// Stamps `cells` onto `state`, centering the pattern's bounding box on the
+/// `width` x `height` viewport (the simulation's plane is unbounded, but the
+/// viewport is the only area the player can see without panning).
+pub fn stamp_centered(state: &mut dyn Simulation, cells: &Cells, width: i64, height: i64) {
+    if cells.is_empty() {
+        return;
+    }
+
+    let min_x = cells.iter().map(|(x, _)| *x).min().unwrap();
+    let max_x = cells.iter().map(|(x, _)| *x).max().unwrap();
+    let min_y = cells.iter().map(|(_, y)| *y).min().unwrap();
+    let max_y = cells.iter().map(|(_, y)| *y).max().unwrap();
+
+    let x_offset = (width - (max_x - min_x + 1)) / 2 - min_x;
+    let y_offset = (height - (max_y - min_y + 1)) / 2 - min_y;
+
+    for (x, y) in cells {
+        state.set(x + x_offset, y + y_offset, true);
+    }
+}
+
+/// Scans `state` for live cells and emits them as RLE, the more compact of
+/// the two formats `parse` understands.
+pub fn export_rle(state: &dyn Simulation) -> String {
+    let live: Cells = state.live_cells().into_iter().collect();
+
+    if live.is_empty() {
+        return "x = 0, y = 0, rule = B3/S23\n!\n".to_string();
+    }
+
+    let min_x = live.iter().map(|(x, _)| *x).min().unwrap();
+    let max_x = live.iter().map(|(x, _)| *x).max().unwrap();
+    let min_y = live.iter().map(|(_, y)| *y).min().unwrap();
+    let max_y = live.iter().map(|(_, y)| *y).max().unwrap();
+
+    let mut body = String::new();
+    for row in min_y..=max_y {
+        let mut col = min_x;
+        while col <= max_x {
+            let alive = live.contains(&(col, row));
+            let run_start = col;
+            while col <= max_x && live.contains(&(col, row)) == alive {
+                col += 1;
+            }
+
+            let run = col - run_start;
+            if run > 1 {
+                body.push_str(&run.to_string());
+            }
+            body.push(if alive { 'o' } else { 'b' });
+        }
+        body.push('$');
+    }
+    body.push('!');
+
+    format!(
+        "x = {}, y = {}, rule = B3/S23\n{}\n",
+        max_x - min_x + 1,
+        max_y - min_y + 1,
+        body
+    )
+}
@@ -0,0 +1,38 @@
+mod hashlife;
+mod sparse;
+
+pub use hashlife::HashlifeSimulation;
+pub use sparse::SparseSimulation;
+
+/// The core Game of Life rules, decoupled from how the live cells are stored.
+/// Coordinates are an unbounded plane (no toroidal wraparound); the UI is
+/// responsible for picking the window of that plane it wants to draw.
+pub trait Simulation {
+    /// Advances the simulation. Implementations are free to advance by more
+    /// than one generation per call (`HashlifeSimulation` jumps by whatever
+    /// power of two its internal quadtree depth affords).
+    fn step(&mut self);
+    fn set(&mut self, x: i64, y: i64, alive: bool);
+    fn live_cells(&self) -> Vec<(i64, i64)>;
+    fn clear(&mut self);
+}
+
+/// Which `Simulation` backend to run. Sparse is the general-purpose default;
+/// Hashlife trades per-generation granularity for enormous speedups on large,
+/// regular patterns that take a long time to stabilize. Because Hashlife's
+/// jump size varies call to call (see `HashlifeSimulation::step`), the `+`/`-`
+/// tick-rate controls and watching individual generations tick by both stop
+/// being meaningful under this backend -- pick it for fast-forwarding, not
+/// for interactive play.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SimulationBackend {
+    Sparse,
+    Hashlife,
+}
+
+pub fn new(backend: SimulationBackend) -> Box<dyn Simulation> {
+    match backend {
+        SimulationBackend::Sparse => Box::new(SparseSimulation::new()),
+        SimulationBackend::Hashlife => Box::new(HashlifeSimulation::new()),
+    }
+}
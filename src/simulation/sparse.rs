@@ -0,0 +1,57 @@
+use super::Simulation;
+use std::collections::{HashMap, HashSet};
+
+/// Stores only live cells, so a step costs work proportional to the
+/// population instead of the area of a fixed-size grid. Each generation
+/// builds a neighbour-count map by incrementing the eight neighbours of
+/// every live cell, then keeps whatever has exactly 3 neighbours plus
+/// whatever was already alive with exactly 2.
+#[derive(Default)]
+pub struct SparseSimulation {
+    live: HashSet<(i64, i64)>,
+}
+
+impl SparseSimulation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Simulation for SparseSimulation {
+    fn step(&mut self) {
+        let mut neighbour_counts: HashMap<(i64, i64), u8> = HashMap::new();
+
+        for &(x, y) in &self.live {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    *neighbour_counts.entry((x + dx, y + dy)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        self.live = neighbour_counts
+            .into_iter()
+            .filter(|&(cell, count)| count == 3 || (count == 2 && self.live.contains(&cell)))
+            .map(|(cell, _)| cell)
+            .collect();
+    }
+
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        if alive {
+            self.live.insert((x, y));
+        } else {
+            self.live.remove(&(x, y));
+        }
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        self.live.iter().copied().collect()
+    }
+
+    fn clear(&mut self) {
+        self.live.clear();
+    }
+}